@@ -33,25 +33,35 @@ impl Lox {
         }
     }
 
-    // fn run_prompt() {
-    //     loop {
-    //         print!("> ");
-    //         io::stdout().flush().unwrap();
-    //
-    //         let mut line = String::new();
-    //         io::stdin().read_line(&mut line).unwrap();
-    //         run(&line);
-    //     }
-    // }
+    pub fn run_prompt(&mut self) {
+        loop {
+            print!("> ");
+            io::stdout().flush().unwrap();
+
+            let mut line = String::new();
+            let bytes = io::stdin().read_line(&mut line).unwrap();
+            if bytes == 0 {
+                // EOF (Ctrl-D) or empty read: leave the session cleanly.
+                break;
+            }
+
+            self.run(line);
+            // A typo on one line shouldn't poison the rest of the session.
+            self.had_error = false;
+        }
+    }
 
     pub fn run(&mut self, source: String) {
-        let mut scanner = build_scanner(source);
-        let tokens = scanner.scan_tokens();
+        let mut scanner = build_scanner(source, None);
+        let (tokens, errors) = scanner.scan_tokens();
 
         for token in tokens {
             println!("{}", token.to_string());
         }
-        if scanner.has_error {
+        for error in &errors {
+            writeln!(io::stderr(), "{}", error).unwrap();
+        }
+        if !errors.is_empty() {
             self.had_error = true;
         }
         println!("EOF  null");
@@ -0,0 +1,18 @@
+use std::env;
+use std::process;
+
+use lox_interpreter::build_lox;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut lox = build_lox();
+
+    match args.len() {
+        1 => lox.run_prompt(),
+        2 => lox.run_file(&args[1]),
+        _ => {
+            eprintln!("Usage: lox [script]");
+            process::exit(64);
+        }
+    }
+}
@@ -1,20 +1,81 @@
-use std::{any::Any, collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display};
+
+use crate::token::{Literal, Token, TokenType, TokenType::*};
+
+/// A lexical error discovered while scanning, carrying enough location
+/// information to point at the exact character that tripped the scanner.
+#[derive(Clone, Debug)]
+pub enum ScanError {
+    UnexpectedChar {
+        path: String,
+        line: usize,
+        col: usize,
+        ch: char,
+    },
+    UnterminatedString {
+        path: String,
+        line: usize,
+        col: usize,
+    },
+    InvalidNumber {
+        path: String,
+        line: usize,
+        col: usize,
+        message: String,
+    },
+    InvalidEscape {
+        path: String,
+        line: usize,
+        col: usize,
+        message: String,
+    },
+}
 
-use crate::token::{Token, TokenType, TokenType::*};
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::UnexpectedChar {
+                path,
+                line,
+                col,
+                ch,
+            } => write!(f, "{}:{}:{}: Unexpected character: {}", path, line, col, ch),
+            ScanError::UnterminatedString { path, line, col } => {
+                write!(f, "{}:{}:{}: Unterminated string.", path, line, col)
+            }
+            ScanError::InvalidNumber {
+                path,
+                line,
+                col,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path, line, col, message),
+            ScanError::InvalidEscape {
+                path,
+                line,
+                col,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path, line, col, message),
+        }
+    }
+}
 
 pub struct Scanner {
     source: String,
     tokens: Vec<Token>,
     keywords: HashMap<String, TokenType>,
+    filename: Option<String>,
 
     start: usize,
+    start_col: usize,
     current: usize,
     line: u32,
+    col: usize,
+    emitted_eof: bool,
 
-    pub has_error: bool,
+    pub errors: Vec<ScanError>,
 }
 
-pub fn build_scanner(source: String) -> Scanner {
+pub fn build_scanner(source: String, filename: Option<String>) -> Scanner {
     let mut keywords = HashMap::new();
     keywords.insert("and".to_string(), AND);
     keywords.insert("class".to_string(), CLASS);
@@ -37,36 +98,70 @@ pub fn build_scanner(source: String) -> Scanner {
         source,
         tokens: Vec::new(),
         keywords,
+        filename,
         start: 0,
+        start_col: 1,
         current: 0,
         line: 1,
-        has_error: false,
+        col: 1,
+        emitted_eof: false,
+        errors: Vec::new(),
     }
 }
 
 impl Scanner {
-    pub fn scan_tokens(&mut self) -> Vec<Token> {
-        // println!("Scanning tokens...");
-        // println!("Source: {}", self.source);
-        while !self.is_at_end() {
+    /// Eagerly drains the scanner into a `Vec`, for callers (such as the
+    /// tree-walk frontend) that want every token up front. Thin wrapper over
+    /// the pull-based [`Scanner::next_token`] iterator.
+    pub fn scan_tokens(&mut self) -> (Vec<Token>, Vec<ScanError>) {
+        let tokens: Vec<Token> = self.by_ref().collect();
+        (tokens, self.errors.clone())
+    }
+
+    /// Pulls exactly one real token, running as many `scan_token` iterations
+    /// as needed to skip whitespace, comments, and error recovery. Emits the
+    /// terminating `EOF` token once and `None` thereafter, so a single-pass
+    /// compiler can drive the lexer on demand.
+    pub fn next_token(&mut self) -> Option<Token> {
+        loop {
+            if self.emitted_eof {
+                return None;
+            }
+            if self.is_at_end() {
+                self.emitted_eof = true;
+                return Some(Token {
+                    token_type: EOF,
+                    lexeme: "".to_string(),
+                    literal: Literal::None,
+                    line: self.line as usize,
+                    col: self.col,
+                });
+            }
+
             // We are at the beginning of the next lexeme.
             self.start = self.current;
+            self.start_col = self.col;
+            let before = self.tokens.len();
             self.scan_token();
+            if self.tokens.len() > before {
+                return self.tokens.pop();
+            }
+            // Whitespace, a comment, or an error produced no token — keep going.
         }
+    }
 
-        self.tokens.push(Token {
-            token_type: EOF,
-            lexeme: "".to_string(),
-            literal: "".to_string(),
-            line: self.line as usize,
-        });
-
-        return self.tokens.clone();
+    /// The source path reported in errors, falling back to a placeholder when
+    /// scanning anonymous input such as a REPL line.
+    fn path(&self) -> String {
+        self.filename
+            .clone()
+            .unwrap_or_else(|| "<unknown>".to_string())
     }
 
     fn scan_token(&mut self) {
         let c = self.source.as_bytes()[self.current] as char;
         self.current += 1;
+        self.col += 1;
 
         match c {
             '(' => self.add_token(LEFT_PAREN),
@@ -117,7 +212,10 @@ impl Scanner {
                 }
             }
             ' ' | '\r' | '\t' => (),
-            '\n' => self.line += 1,
+            '\n' => {
+                self.line += 1;
+                self.col = 1;
+            }
 
             // literals
             '"' => self.string(),
@@ -134,9 +232,12 @@ impl Scanner {
                 } else if is_alpha(c) {
                     self.identifier();
                 } else {
-                    // Lox class error here ? how ??
-                    eprintln!("[line {}] Error: Unexpected character: {}", self.line, c);
-                    self.has_error = true;
+                    self.errors.push(ScanError::UnexpectedChar {
+                        path: self.path(),
+                        line: self.line as usize,
+                        col: self.start_col,
+                        ch: c,
+                    });
                 }
             }
         }
@@ -149,7 +250,12 @@ impl Scanner {
 
         let text = &self.source[self.start..self.current];
         let token_type = self.keywords.get(text).copied().unwrap_or(IDENTIFIER);
-        self.add_token(token_type);
+        match token_type {
+            TRUE => self.add_token_literal(token_type, Literal::Bool(true)),
+            FALSE => self.add_token_literal(token_type, Literal::Bool(false)),
+            NIL => self.add_token_literal(token_type, Literal::Nil),
+            _ => self.add_token(token_type),
+        }
     }
 
     fn is_at_end(&self) -> bool {
@@ -159,6 +265,7 @@ impl Scanner {
     // methods for moving through the source
     fn advance(&mut self) {
         self.current += 1;
+        self.col += 1;
         // self.source.chars().nth(self.current - 1).unwrap()
     }
 
@@ -171,6 +278,7 @@ impl Scanner {
         }
 
         self.current += 1;
+        self.col += 1;
         true
     }
 
@@ -191,50 +299,161 @@ impl Scanner {
     // methods for token handling
     fn add_token(&mut self, token_type: TokenType) {
         // println!("Adding token: {:?}", token_type);
-        self.add_token_literal::<String>(token_type, None)
+        self.add_token_literal(token_type, Literal::None)
     }
 
-    fn add_token_literal<T: ToString + Display>(
-        &mut self,
-        token_type: TokenType,
-        literal: Option<T>,
-    ) {
+    fn add_token_literal(&mut self, token_type: TokenType, literal: Literal) {
         let text = &self.source[self.start..self.current];
         let token = Token {
             token_type,
             lexeme: text.to_string(),
-            literal: match literal {
-                Some(l) => with_decimal(l),
-                None => "".to_string(),
-            },
+            literal,
             line: self.line as usize,
+            col: self.start_col,
         };
         self.tokens.push(token);
     }
 
     //
     fn string(&mut self) {
+        let mut value = String::new();
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
+            let c = self.peek();
+            if c == '\n' {
                 self.line += 1;
+                self.col = 0;
+                value.push(c);
+                self.advance();
+            } else if c == '\\' {
+                let esc_col = self.col;
+                self.advance();
+                if self.is_at_end() {
+                    break;
+                }
+                let esc = self.peek();
+                self.advance();
+                match esc {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    'r' => value.push('\r'),
+                    '\\' => value.push('\\'),
+                    '"' => value.push('"'),
+                    '0' => value.push('\0'),
+                    'u' => match self.unicode_escape() {
+                        Some(ch) => value.push(ch),
+                        None => self.errors.push(ScanError::InvalidEscape {
+                            path: self.path(),
+                            line: self.line as usize,
+                            col: esc_col,
+                            message: "Invalid unicode escape.".to_string(),
+                        }),
+                    },
+                    other => self.errors.push(ScanError::InvalidEscape {
+                        path: self.path(),
+                        line: self.line as usize,
+                        col: esc_col,
+                        message: format!("Unknown escape sequence: \\{}", other),
+                    }),
+                }
+            } else {
+                value.push(c);
+                self.advance();
             }
-            self.advance();
         }
 
         if self.is_at_end() {
-            eprintln!("[line {}] Error: Unterminated string.", self.line);
-            self.has_error = true;
+            self.errors.push(ScanError::UnterminatedString {
+                path: self.path(),
+                line: self.line as usize,
+                col: self.start_col,
+            });
             return;
         }
 
         self.advance();
 
-        let value = self.source[self.start + 1..self.current - 1].to_string();
-        self.add_token_literal(STRING, Some(value));
+        self.add_token_literal(STRING, Literal::Str(value));
+    }
+
+    /// Decodes a `\u{...}` escape, assuming the leading `\u` has already been
+    /// consumed. Returns `None` on a missing brace, non-hex body, or a code
+    /// point outside the Unicode scalar range.
+    fn unicode_escape(&mut self) -> Option<char> {
+        if self.peek() != '{' {
+            return None;
+        }
+        self.advance();
+
+        let mut hex = String::new();
+        // Keep recovery inside the string literal: stop at the closing quote or
+        // a newline, and cap at the 6 hex digits a scalar value can need.
+        while self.peek() != '}'
+            && self.peek() != '"'
+            && self.peek() != '\n'
+            && !self.is_at_end()
+            && hex.len() < 6
+        {
+            hex.push(self.peek());
+            self.advance();
+        }
+
+        if self.peek() != '}' {
+            return None;
+        }
+        self.advance();
+
+        let code = u32::from_str_radix(&hex, 16).ok()?;
+        char::from_u32(code)
     }
 
     fn number(&mut self) {
-        while is_digit(self.peek()) {
+        let first = self.source.as_bytes()[self.start] as char;
+
+        // Radix-prefixed integer literals: 0x.. / 0b.. / 0o..
+        if first == '0' {
+            if let Some(base) = match self.peek() {
+                'x' | 'X' => Some(16u32),
+                'b' | 'B' => Some(2),
+                'o' | 'O' => Some(8),
+                _ => None,
+            } {
+                // consume the radix letter
+                self.advance();
+                let digits_start = self.current;
+                while is_in_base(self.peek(), base) || self.peek() == '_' {
+                    self.advance();
+                }
+
+                let raw = &self.source[digits_start..self.current];
+                if !valid_digit_run(raw, base) {
+                    self.errors.push(ScanError::InvalidNumber {
+                        path: self.path(),
+                        line: self.line as usize,
+                        col: self.start_col,
+                        message: format!("Invalid digits for base-{} literal.", base),
+                    });
+                    return;
+                }
+
+                let cleaned: String = raw.chars().filter(|c| *c != '_').collect();
+                let value = match i64::from_str_radix(&cleaned, base) {
+                    Ok(v) => v as f64,
+                    Err(_) => {
+                        self.errors.push(ScanError::InvalidNumber {
+                            path: self.path(),
+                            line: self.line as usize,
+                            col: self.start_col,
+                            message: format!("base-{} literal out of range.", base),
+                        });
+                        return;
+                    }
+                };
+                self.add_token_literal(NUMBER, Literal::Number(value));
+                return;
+            }
+        }
+
+        while is_digit(self.peek()) || self.peek() == '_' {
             self.advance();
         }
 
@@ -242,18 +461,37 @@ impl Scanner {
         if self.peek() == '.' && is_digit(self.peek_next()) {
             // consume the '.'
             self.advance();
-            while is_digit(self.peek()) {
+            while is_digit(self.peek()) || self.peek() == '_' {
                 self.advance();
             }
         }
 
         let src = &self.source[self.start..self.current];
-        let f: f64 = match src.parse() {
+        if !valid_digit_run(src, 10) {
+            self.errors.push(ScanError::InvalidNumber {
+                path: self.path(),
+                line: self.line as usize,
+                col: self.start_col,
+                message: "Underscores must sit between digits.".to_string(),
+            });
+            return;
+        }
+
+        let cleaned: String = src.chars().filter(|c| *c != '_').collect();
+        let f: f64 = match cleaned.parse() {
             Ok(f) => f,
             // ERR HANDLE
             Err(_) => panic!("Error parsing number"),
         };
-        self.add_token_literal(NUMBER, Some(f + 0.0));
+        self.add_token_literal(NUMBER, Literal::Number(f));
+    }
+}
+
+impl Iterator for Scanner {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.next_token()
     }
 }
 
@@ -269,21 +507,36 @@ fn is_digit(c: char) -> bool {
     c >= '0' && c <= '9'
 }
 
-fn with_decimal<T: Display>(value: T) -> String {
-    let formatted = format!("{:.1}", value);
-
-    if formatted.ends_with(".0") {
-        formatted
-    } else {
-        return value.to_string();
+fn is_in_base(c: char, base: u32) -> bool {
+    match base {
+        2 => matches!(c, '0'..='1'),
+        8 => matches!(c, '0'..='7'),
+        16 => matches!(c, '0'..='9' | 'a'..='f' | 'A'..='F'),
+        _ => matches!(c, '0'..='9'),
     }
 }
 
-fn is_float<T: Any>(value: &T) -> bool {
-    let value_any: &dyn Any = value;
-    if value_any.is::<f32>() || value_any.is::<f64>() {
-        return true;
-    } else {
-        return false;
+/// Validates the digit run of a numeric literal: it must hold at least one
+/// base digit, and every `_` separator must sit directly between two digits
+/// (never leading, trailing, or doubled). A decimal point is permitted but is
+/// not itself a digit, so `1_.0` and `1._0` are rejected.
+fn valid_digit_run(s: &str, base: u32) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    let mut digit_count = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            let prev = if i > 0 { chars.get(i - 1).copied() } else { None };
+            let next = chars.get(i + 1).copied();
+            if !(prev.is_some_and(|p| is_in_base(p, base))
+                && next.is_some_and(|n| is_in_base(n, base)))
+            {
+                return false;
+            }
+        } else if is_in_base(c, base) {
+            digit_count += 1;
+        } else if c != '.' {
+            return false;
+        }
     }
+    digit_count > 0
 }
@@ -49,21 +49,50 @@ pub enum TokenType {
     EOF,
 }
 
+use std::fmt::Display;
+
+/// A literal value carried by a token once the scanner has interpreted its
+/// lexeme. Tokens without a literal payload use [`Literal::None`].
+#[derive(Clone, Debug)]
+pub enum Literal {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Nil,
+    None,
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // Canonical Lox rendering: integral values keep a single trailing
+            // ".0" while genuine fractions print in full.
+            Literal::Number(n) => {
+                if n.fract() == 0.0 {
+                    write!(f, "{:.1}", n)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
+            Literal::Str(s) => write!(f, "{}", s),
+            Literal::Bool(b) => write!(f, "{}", b),
+            Literal::Nil => write!(f, "nil"),
+            Literal::None => write!(f, "null"),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
-    pub literal: String,
+    pub literal: Literal,
     pub line: usize,
+    pub col: usize,
 }
 
 impl Token {
     pub fn to_string(&self) -> String {
-        let literal = if self.literal.is_empty() {
-            "null".to_string()
-        } else {
-            self.literal.clone()
-        };
-        format!("{:?} {} {}", self.token_type, self.lexeme, literal)
+        format!("{:?} {} {}", self.token_type, self.lexeme, self.literal)
     }
 }